@@ -1,4 +1,9 @@
-use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+use std::{
+    borrow::{Borrow, Cow},
+    collections::HashMap,
+    hash::Hash,
+    ops::Range,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -19,7 +24,8 @@ impl<'a> AsciiKeywords<'a> {
 }
 
 impl<'a> Iterator for AsciiKeywords<'a> {
-    type Item = &'a str;
+    /// Yields each keyword together with its byte offset within the source string.
+    type Item = (usize, &'a str);
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.index >= self.s.len() {
@@ -47,7 +53,44 @@ impl<'a> Iterator for AsciiKeywords<'a> {
             self.index += 1;
         }
 
-        Some(keyword)
+        Some((start, keyword))
+    }
+}
+
+/// An iterator over Unicode keywords in a string.
+struct UnicodeKeywords<'a> {
+    s: &'a str,
+    index: usize,
+}
+
+impl<'a> UnicodeKeywords<'a> {
+    /// Creates a new `UnicodeKeywords` iterator from a string slice.
+    fn new(s: &'a str) -> Self {
+        UnicodeKeywords { s, index: 0 }
+    }
+}
+
+impl<'a> Iterator for UnicodeKeywords<'a> {
+    /// Yields each keyword together with its byte offset within the source string.
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.s[self.index..];
+
+        // Skip leading non-alphanumeric characters to reach the start of the next keyword.
+        let start_rel = rest.char_indices().find(|(_, c)| c.is_alphanumeric())?.0;
+        let start = self.index + start_rel;
+
+        // Consume the run of alphanumeric characters.
+        let run = &self.s[start..];
+        let end_rel = run
+            .char_indices()
+            .find(|(_, c)| !c.is_alphanumeric())
+            .map_or(run.len(), |(i, _)| i);
+        let end = start + end_rel;
+
+        self.index = end;
+        Some((start, &self.s[start..end]))
     }
 }
 
@@ -72,34 +115,85 @@ pub trait Keywords {
     /// assert_eq!(Some("123"), keywords.next());
     /// assert_eq!(None, keywords.next());
     /// ```
-    fn ascii_keywords(&self) -> impl Iterator<Item = &str> + '_;
+    fn ascii_keywords(&self) -> impl Iterator<Item = &str> + '_ {
+        self.ascii_keyword_spans().map(|(_, keyword)| keyword)
+    }
+
+    /// Returns an iterator over the ASCII keywords in the string, each paired with its byte offset
+    /// within the string.
+    ///
+    /// This is the keyword-splitting primitive; [`ascii_keywords`](Keywords::ascii_keywords) is
+    /// defined in terms of it. The offset locates the keyword in the original string, which lets
+    /// callers recover the exact span that produced a match (e.g. for highlighting).
+    fn ascii_keyword_spans(&self) -> impl Iterator<Item = (usize, &str)> + '_;
+
+    /// Returns an iterator over the Unicode keywords in the string.
+    ///
+    /// Unlike [`ascii_keywords`](Keywords::ascii_keywords), a keyword is any maximal run of
+    /// characters for which [`char::is_alphanumeric`] holds, so accented Latin, Cyrillic, CJK, and
+    /// other scripts are recognized instead of being dropped. Runs are separated by any
+    /// non-alphanumeric character.
+    ///
+    /// Example usage:
+    /// ```
+    /// use keywords::Keywords;
+    ///
+    /// let text = "café—Москва";
+    /// let mut keywords = text.unicode_keywords();
+    ///
+    /// assert_eq!(Some("café"), keywords.next());
+    /// assert_eq!(Some("Москва"), keywords.next());
+    /// assert_eq!(None, keywords.next());
+    /// ```
+    fn unicode_keywords(&self) -> impl Iterator<Item = &str> + '_ {
+        self.unicode_keyword_spans().map(|(_, keyword)| keyword)
+    }
+
+    /// Returns an iterator over the Unicode keywords in the string, each paired with its byte
+    /// offset within the string.
+    ///
+    /// This is the Unicode counterpart of
+    /// [`ascii_keyword_spans`](Keywords::ascii_keyword_spans); `unicode_keywords` is defined in
+    /// terms of it.
+    fn unicode_keyword_spans(&self) -> impl Iterator<Item = (usize, &str)> + '_;
 }
 
 impl Keywords for &str {
     #[inline]
-    fn ascii_keywords(&self) -> impl Iterator<Item = &str> + '_ {
+    fn ascii_keyword_spans(&self) -> impl Iterator<Item = (usize, &str)> + '_ {
         AsciiKeywords::new(self)
     }
+
+    #[inline]
+    fn unicode_keyword_spans(&self) -> impl Iterator<Item = (usize, &str)> + '_ {
+        UnicodeKeywords::new(self)
+    }
 }
 
 impl Keywords for String {
     #[inline]
-    fn ascii_keywords(&self) -> impl Iterator<Item = &str> + '_ {
+    fn ascii_keyword_spans(&self) -> impl Iterator<Item = (usize, &str)> + '_ {
         AsciiKeywords::new(self)
     }
+
+    #[inline]
+    fn unicode_keyword_spans(&self) -> impl Iterator<Item = (usize, &str)> + '_ {
+        UnicodeKeywords::new(self)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Match<V> {
     Exact(V),
     Prefix(V),
+    Fuzzy { distance: u8, value: V },
 }
 
 impl<V> Match<V> {
     /// Extracts the inner value from the `Match`.
     pub fn into_inner(self) -> V {
         match self {
-            Match::Exact(v) | Match::Prefix(v) => v,
+            Match::Exact(v) | Match::Prefix(v) | Match::Fuzzy { value: v, .. } => v,
         }
     }
 }
@@ -107,7 +201,7 @@ impl<V> Match<V> {
 impl<V> AsRef<V> for Match<V> {
     fn as_ref(&self) -> &V {
         match self {
-            Match::Exact(v) | Match::Prefix(v) => v,
+            Match::Exact(v) | Match::Prefix(v) | Match::Fuzzy { value: v, .. } => v,
         }
     }
 }
@@ -117,11 +211,23 @@ where
     V: PartialOrd,
 {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        use std::cmp::Ordering;
         match (self, other) {
             (Match::Exact(a), Match::Exact(b)) => a.partial_cmp(b),
             (Match::Prefix(a), Match::Prefix(b)) => a.partial_cmp(b),
-            (Match::Exact(_), Match::Prefix(_)) => Some(std::cmp::Ordering::Less),
-            (Match::Prefix(_), Match::Exact(_)) => Some(std::cmp::Ordering::Greater),
+            (
+                Match::Fuzzy { distance: a, value: av },
+                Match::Fuzzy { distance: b, value: bv },
+            ) => match a.cmp(b) {
+                Ordering::Equal => av.partial_cmp(bv),
+                ord => Some(ord),
+            },
+            // An exact match is always the strongest, followed by a prefix match,
+            // with fuzzy matches ranked last.
+            (Match::Exact(_), _) => Some(Ordering::Less),
+            (_, Match::Exact(_)) => Some(Ordering::Greater),
+            (Match::Prefix(_), _) => Some(Ordering::Less),
+            (_, Match::Prefix(_)) => Some(Ordering::Greater),
         }
     }
 }
@@ -135,6 +241,18 @@ where
     }
 }
 
+/// Selects how keys are split into keywords when feeding the keyword index.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Tokenizer {
+    /// Split on ASCII alphabetic and numeric runs (see [`Keywords::ascii_keywords`]).
+    #[default]
+    Ascii,
+    /// Split on Unicode alphanumeric runs (see [`Keywords::unicode_keywords`]).
+    Unicode,
+    /// Like [`Tokenizer::Unicode`], but case-folds keywords for case-insensitive matching.
+    UnicodeCaseInsensitive,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct KeywordMap<K, V>
 where
@@ -142,47 +260,128 @@ where
 {
     data: Vec<V>,
     keys: HashMap<K, usize>,
-    keyword_index: Vec<(String, usize)>,
+    /// Sorted by keyword; each entry is `(keyword, value index, byte offset of the keyword in its
+    /// key string)`. The offset lets matches report the exact span that produced them.
+    keyword_index: Vec<(String, usize, usize)>,
+    /// Tokenizer used to split keys into keywords. Defaults to [`Tokenizer::Ascii`].
+    #[serde(default)]
+    tokenizer: Tokenizer,
 }
 
 impl<K, V> KeywordMap<K, V>
 where
     K: Keywords + Hash + Eq,
 {
-    /// Creates a new `KeywordMap`.
+    /// Creates a new `KeywordMap` using the default ([`Tokenizer::Ascii`]) tokenizer.
     pub fn new() -> Self {
+        Self::with_tokenizer(Tokenizer::Ascii)
+    }
+
+    /// Creates a new `KeywordMap` that splits keys into keywords using `tokenizer`.
+    ///
+    /// Use [`Tokenizer::Unicode`] (or [`Tokenizer::UnicodeCaseInsensitive`]) to index
+    /// international corpora whose keywords contain non-ASCII characters.
+    pub fn with_tokenizer(tokenizer: Tokenizer) -> Self {
         KeywordMap {
             data: Vec::new(),
             keys: HashMap::new(),
             keyword_index: Vec::new(),
+            tokenizer,
         }
     }
 
     /// Inserts a key-value pair into the `KeywordMap`.
     pub fn insert(&mut self, key: K, value: V) {
         let index = self.data.len();
-        for keyword in key.ascii_keywords() {
-            self.keyword_index.push((keyword.to_string(), index));
+        match self.tokenizer {
+            Tokenizer::Ascii => {
+                for (offset, keyword) in key.ascii_keyword_spans() {
+                    self.index_keyword(keyword.to_string(), index, offset);
+                }
+            }
+            Tokenizer::Unicode => {
+                for (offset, keyword) in key.unicode_keyword_spans() {
+                    self.index_keyword(keyword.to_string(), index, offset);
+                }
+            }
+            Tokenizer::UnicodeCaseInsensitive => {
+                for (offset, keyword) in key.unicode_keyword_spans() {
+                    self.index_keyword(keyword.to_lowercase(), index, offset);
+                }
+            }
         }
 
         self.data.push(value);
         self.keys.insert(key, index);
     }
 
+    /// Inserts a single keyword into the sorted keyword index.
+    ///
+    /// Keeps `keyword_index` sorted by keyword via binary-search insertion; inserting after any
+    /// equal keywords preserves insertion order within a run of duplicates.
+    fn index_keyword(&mut self, keyword: String, index: usize, offset: usize) {
+        let pos = self
+            .keyword_index
+            .partition_point(|(k, _, _)| k.as_str() <= keyword.as_str());
+        self.keyword_index.insert(pos, (keyword, index, offset));
+    }
+
     /// Removes a key-value pair from the `KeywordMap` by its key.
-    pub fn remove<Q: ?Sized>(&mut self, key: &Q) -> Option<V>
+    ///
+    /// This uses a swap-remove strategy, so the last value in the map is moved into the vacated
+    /// slot rather than shifting every later element down. Compared to
+    /// [`remove_preserve_order`](Self::remove_preserve_order) this avoids the data-vector shift and
+    /// the decrement-every-index reindex pass, repointing only the single moved slot; it still
+    /// scans the keyword index once to drop the removed element's entries, so removal remains
+    /// linear in the total number of indexed keywords. The trade-off is that it changes the
+    /// ordering of the remaining values — callers that need stable iteration order should use
+    /// [`remove_preserve_order`](Self::remove_preserve_order) instead.
+    pub fn remove<Q: ?Sized + Hash + Eq>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.keys.remove(key.borrow())?;
+        let last = self.data.len() - 1;
+        let value = self.data.swap_remove(index);
+
+        // Drop the removed element's own keyword entries.
+        self.keyword_index.retain(|(_, idx, _)| *idx != index);
+
+        // The element formerly at `last` now lives at `index`; repoint only the entries that
+        // referred to the moved slot.
+        if index != last {
+            for (_, idx) in self.keys.iter_mut() {
+                if *idx == last {
+                    *idx = index;
+                }
+            }
+            for (_, idx, _) in &mut self.keyword_index {
+                if *idx == last {
+                    *idx = index;
+                }
+            }
+        }
+
+        Some(value)
+    }
+
+    /// Removes a key-value pair from the `KeywordMap` by its key, preserving the relative order of
+    /// the remaining values.
+    ///
+    /// Unlike [`remove`](Self::remove), this shifts every later element down by one and reindexes
+    /// the whole map, so it costs `O(n)`. Prefer it only when stable iteration order matters.
+    pub fn remove_preserve_order<Q: ?Sized + Hash + Eq>(&mut self, key: &Q) -> Option<V>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
     {
         let index = self.keys.remove(key.borrow())?;
         let value = self.data.remove(index);
 
         // Update the keyword index
-        self.keyword_index.retain(|(_, idx)| *idx != index);
+        self.keyword_index.retain(|(_, idx, _)| *idx != index);
 
         // Adjust indices in the keyword index
-        for (_, idx) in &mut self.keyword_index {
+        for (_, idx, _) in &mut self.keyword_index {
             if *idx > index {
                 *idx -= 1;
             }
@@ -199,10 +398,9 @@ where
     }
 
     /// Retrieves a value by its key.
-    pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&V>
+    pub fn get<Q: ?Sized + Hash + Eq>(&self, key: &Q) -> Option<&V>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
     {
         self.keys
             .get(key.borrow())
@@ -210,10 +408,9 @@ where
     }
 
     /// Retrieves a mutable reference to a value by its key.
-    pub fn get_mut<Q: ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    pub fn get_mut<Q: ?Sized + Hash + Eq>(&mut self, key: &Q) -> Option<&mut V>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
     {
         self.keys
             .get(key.borrow())
@@ -225,32 +422,300 @@ impl<K, V> KeywordMap<K, V>
 where
     K: Keywords + Hash + Eq + Borrow<str>,
 {
+    /// Folds a query so it matches the map's tokenizer.
+    ///
+    /// For [`Tokenizer::UnicodeCaseInsensitive`] the keywords were case-folded at insertion time,
+    /// so the query is lowercased here; otherwise it is returned unchanged. This keeps
+    /// case-insensitive lookups working without requiring callers to pre-fold the query.
+    fn fold_query<'q>(&self, query: &'q str) -> Cow<'q, str> {
+        match self.tokenizer {
+            Tokenizer::UnicodeCaseInsensitive => Cow::Owned(query.to_lowercase()),
+            Tokenizer::Ascii | Tokenizer::Unicode => Cow::Borrowed(query),
+        }
+    }
+
     pub fn find_by_partial_keyword<'a>(
         &'a self,
         keyword: &str,
     ) -> impl Iterator<Item = Match<&'a V>> {
         let exact_match = self.keys.get(keyword).copied();
 
-        let iter = self
+        let folded = self.fold_query(keyword);
+        let keyword = folded.as_ref();
+
+        // Since `keyword_index` is sorted by keyword, the entries sharing `keyword` as a prefix
+        // form a contiguous range. Binary-search its bounds instead of scanning the whole index.
+        let lo = self
             .keyword_index
+            .partition_point(|(k, _, _)| k.as_str() < keyword);
+        let hi = match prefix_upper_bound(keyword) {
+            Some(upper) => self
+                .keyword_index
+                .partition_point(|(k, _, _)| k.as_bytes() < upper.as_slice()),
+            None => self.keyword_index.len(),
+        };
+
+        // Dedup value indices within the range so a value indexed under several matching keywords
+        // is yielded once, and drop the exact match (surfaced separately ahead of the range).
+        let mut seen = std::collections::HashSet::new();
+        let iter = self.keyword_index[lo..hi]
             .iter()
-            .filter(move |(k, _)| k.starts_with(keyword))
-            .filter_map(move |(_, index)| {
-                if let Some(exact_match_index) = exact_match {
-                    if *index == exact_match_index {
-                        return None; // Skip exact match if already found
-                    }
+            .filter_map(move |(_, index, _)| {
+                if Some(*index) == exact_match || !seen.insert(*index) {
+                    return None;
                 }
-
-                Some(*index)
-            })
-            .map(|index| Match::Prefix(&self.data[index]));
+                Some(Match::Prefix(&self.data[*index]))
+            });
 
         exact_match
             .into_iter()
             .map(|index| Match::Exact(&self.data[index]))
             .chain(iter)
     }
+
+    /// Finds values by keyword prefix, reporting the specific keyword that matched and its span.
+    ///
+    /// Like [`find_by_partial_keyword`](Self::find_by_partial_keyword), but yields, alongside each
+    /// matched value, the indexed keyword that produced the hit and its byte range within the key
+    /// string. This lets callers wrap the matching substring in highlight markers. Unlike
+    /// `find_by_partial_keyword` no deduplication is performed, so every matching keyword yields a
+    /// span.
+    ///
+    /// Note the [`Match::Exact`] semantics differ from
+    /// [`find_by_partial_keyword`](Self::find_by_partial_keyword): here a hit is `Exact` when the
+    /// matched *keyword* equals `query`, whereas `find_by_partial_keyword` reports `Exact` only
+    /// when the *whole key* equals `query`. A value may therefore be classified differently by the
+    /// two methods.
+    pub fn find_with_spans<'a>(
+        &'a self,
+        query: &str,
+    ) -> impl Iterator<Item = (Match<&'a V>, &'a str, Range<usize>)> {
+        let folded = self.fold_query(query);
+        let query = folded.as_ref();
+
+        // The keywords sharing `query` as a prefix form a contiguous, sorted range.
+        let lo = self
+            .keyword_index
+            .partition_point(|(k, _, _)| k.as_str() < query);
+        let hi = match prefix_upper_bound(query) {
+            Some(upper) => self
+                .keyword_index
+                .partition_point(|(k, _, _)| k.as_bytes() < upper.as_slice()),
+            None => self.keyword_index.len(),
+        };
+
+        // Within the range every keyword starts with `query`, so an equal length means an exact
+        // keyword match; capture only the length to avoid borrowing `query` past this call.
+        let query_len = query.len();
+        self.keyword_index[lo..hi]
+            .iter()
+            .map(move |(keyword, index, offset)| {
+                let value = &self.data[*index];
+                let m = if keyword.len() == query_len {
+                    Match::Exact(value)
+                } else {
+                    Match::Prefix(value)
+                };
+                (m, keyword.as_str(), *offset..*offset + keyword.len())
+            })
+    }
+
+    /// Finds values by keyword prefix and ranks them by relevance to `query`.
+    ///
+    /// Prefix and exact hits are grouped by value, so every distinct matching keyword of a value's
+    /// key adds to that value's score. Each value's score combines three
+    /// signals: an exact keyword match outweighs a prefix-only match, a larger number of distinct
+    /// matching keywords raises the score, and a longer matched keyword (relative to the query)
+    /// contributes more, prioritizing the longest overlap. Values are returned once each, sorted by
+    /// descending score, making `KeywordMap` usable as a small autocomplete backend.
+    ///
+    /// As in [`find_with_spans`](Self::find_with_spans), a hit is classified [`Match::Exact`] when
+    /// the matched *keyword* equals `query`, not when the whole key does (the rule used by
+    /// [`find_by_partial_keyword`](Self::find_by_partial_keyword)).
+    pub fn find_ranked<'a>(&'a self, query: &str) -> Vec<(Match<&'a V>, f32)> {
+        /// Weight given to a value that has a keyword matching `query` exactly.
+        const EXACT_WEIGHT: f32 = 10.0;
+
+        let folded = self.fold_query(query);
+        let query = folded.as_ref();
+
+        let lo = self
+            .keyword_index
+            .partition_point(|(k, _, _)| k.as_str() < query);
+        let hi = match prefix_upper_bound(query) {
+            Some(upper) => self
+                .keyword_index
+                .partition_point(|(k, _, _)| k.as_bytes() < upper.as_slice()),
+            None => self.keyword_index.len(),
+        };
+        let query_len = query.len().max(1) as f32;
+
+        struct Group<'a> {
+            exact: bool,
+            keywords: std::collections::HashSet<&'a str>,
+            longest: usize,
+        }
+
+        let mut groups: HashMap<usize, Group<'a>> = HashMap::new();
+        for (keyword, index, _) in &self.keyword_index[lo..hi] {
+            let group = groups.entry(*index).or_insert_with(|| Group {
+                exact: false,
+                keywords: std::collections::HashSet::new(),
+                longest: 0,
+            });
+            group.exact |= keyword.len() == query.len();
+            group.keywords.insert(keyword.as_str());
+            group.longest = group.longest.max(keyword.len());
+        }
+
+        let mut ranked: Vec<(Match<&'a V>, f32)> = groups
+            .into_iter()
+            .map(|(index, group)| {
+                let score = if group.exact { EXACT_WEIGHT } else { 0.0 }
+                    + group.keywords.len() as f32
+                    + group.longest as f32 / query_len;
+                let value = &self.data[index];
+                let m = if group.exact {
+                    Match::Exact(value)
+                } else {
+                    Match::Prefix(value)
+                };
+                (m, score)
+            })
+            .collect();
+
+        // Lift exact matches above the best prefix-only score, so an exact keyword match always
+        // outranks a value matched only by prefix, however many or long its prefix hits are.
+        let prefix_best = ranked
+            .iter()
+            .filter(|(m, _)| !matches!(m, Match::Exact(_)))
+            .fold(0.0_f32, |best, (_, score)| best.max(*score));
+        for (m, score) in &mut ranked {
+            if matches!(m, Match::Exact(_)) {
+                *score += prefix_best;
+            }
+        }
+
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked
+    }
+
+    /// Finds values whose indexed keywords lie within `max_distance` edits of `keyword`.
+    ///
+    /// Matching uses the Levenshtein distance (insertions, deletions, and substitutions), so
+    /// minor spelling mistakes in a query still resolve (e.g. `"temprature"` finds a value
+    /// indexed under `"temperature"`). Results are returned sorted by ascending distance, so
+    /// exact (distance `0`) matches come first, and a value indexed under several keywords
+    /// appears once at its best distance.
+    pub fn find_by_fuzzy_keyword<'a>(
+        &'a self,
+        keyword: &str,
+        max_distance: u8,
+    ) -> Vec<Match<&'a V>> {
+        let folded = self.fold_query(keyword);
+        let query = folded.as_bytes();
+        let max = max_distance as usize;
+
+        // Best distance seen for each value index, so a value indexed under multiple keywords
+        // is reported once at its closest match.
+        let mut best: HashMap<usize, u8> = HashMap::new();
+        for (k, index, _) in &self.keyword_index {
+            // Cheap length-difference prefilter rejects most candidates before any DP.
+            if k.len().abs_diff(query.len()) > max {
+                continue;
+            }
+            if let Some(distance) = banded_edit_distance(k.as_bytes(), query, max) {
+                let distance = distance as u8;
+                best.entry(*index)
+                    .and_modify(|d| {
+                        if distance < *d {
+                            *d = distance;
+                        }
+                    })
+                    .or_insert(distance);
+            }
+        }
+
+        let mut matches: Vec<Match<&'a V>> = best
+            .into_iter()
+            .map(|(index, distance)| {
+                if distance == 0 {
+                    Match::Exact(&self.data[index])
+                } else {
+                    Match::Fuzzy {
+                        distance,
+                        value: &self.data[index],
+                    }
+                }
+            })
+            .collect();
+
+        matches.sort_by_key(|m| match m {
+            Match::Exact(_) => 0,
+            Match::Fuzzy { distance, .. } => *distance as u16,
+            // `Prefix` is never produced here, but rank it after any fuzzy hit for completeness.
+            Match::Prefix(_) => u16::MAX,
+        });
+        matches
+    }
+}
+
+/// Computes the exclusive upper bound of the byte range covering every string that has `prefix`
+/// as a prefix, by incrementing the prefix's last byte.
+///
+/// Returns `None` when `prefix` is empty or consists entirely of `0xFF` bytes, in which case the
+/// range extends to the end of the index.
+fn prefix_upper_bound(prefix: &str) -> Option<Vec<u8>> {
+    let mut bytes = prefix.as_bytes().to_vec();
+    while let Some(last) = bytes.last_mut() {
+        if *last < 0xFF {
+            *last += 1;
+            return Some(bytes);
+        }
+        bytes.pop();
+    }
+    None
+}
+
+/// Computes the Levenshtein distance between `a` and `b`, returning `None` as soon as it is
+/// provably greater than `max`.
+///
+/// Only the diagonal band of width `2 * max + 1` of the DP matrix is filled, and a candidate is
+/// abandoned early once every cell in a row exceeds `max`, so each comparison computes only
+/// `O(max * len)` cells (each row buffer is still allocated at full length).
+fn banded_edit_distance(a: &[u8], b: &[u8], max: usize) -> Option<usize> {
+    let (n, m) = (a.len(), b.len());
+    if n.abs_diff(m) > max {
+        return None;
+    }
+
+    // A sentinel one past `max` marks cells outside the band (or known-too-expensive cells); it
+    // never wins a `min`, so it keeps the recurrence correct without special-casing the edges.
+    let inf = max + 1;
+    let mut prev: Vec<usize> = (0..=m).map(|j| if j <= max { j } else { inf }).collect();
+
+    for i in 1..=n {
+        let mut cur = vec![inf; m + 1];
+        cur[0] = if i <= max { i } else { inf };
+        let lo = i.saturating_sub(max).max(1);
+        let hi = (i + max).min(m);
+        let mut row_min = cur[0];
+        for j in lo..=hi {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let del = prev[j].saturating_add(1);
+            let ins = cur[j - 1].saturating_add(1);
+            let sub = prev[j - 1].saturating_add(cost);
+            cur[j] = del.min(ins).min(sub).min(inf);
+            row_min = row_min.min(cur[j]);
+        }
+        if row_min > max {
+            return None;
+        }
+        prev = cur;
+    }
+
+    let distance = prev[m];
+    (distance <= max).then_some(distance)
 }
 
 #[cfg(test)]
@@ -279,6 +744,35 @@ mod tests {
         assert_eq!(map.get(&"testing123"), Some(&2));
     }
 
+    #[test]
+    fn test_keyword_map_swap_remove_remaps_moved_value() {
+        let mut map = KeywordMap::new();
+        map.insert("alpha", 1);
+        map.insert("beta", 2);
+        map.insert("gamma", 3);
+
+        // Removing a non-last element swaps the last one into its slot; every remaining key must
+        // still resolve to the correct value, and its keywords must still be searchable.
+        assert_eq!(map.remove(&"alpha"), Some(1));
+        assert_eq!(map.get(&"beta"), Some(&2));
+        assert_eq!(map.get(&"gamma"), Some(&3));
+
+        let results: Vec<_> = map.find_by_partial_keyword("gamma").collect();
+        assert_eq!(results, vec![Match::Exact(&3)]);
+    }
+
+    #[test]
+    fn test_keyword_map_remove_preserve_order() {
+        let mut map = KeywordMap::new();
+        map.insert("alpha", 1);
+        map.insert("beta", 2);
+        map.insert("gamma", 3);
+
+        assert_eq!(map.remove_preserve_order(&"beta"), Some(2));
+        assert_eq!(map.get(&"alpha"), Some(&1));
+        assert_eq!(map.get(&"gamma"), Some(&3));
+    }
+
     #[test]
     fn test_keyword_map_find_by_keyword() {
         let mut map = KeywordMap::new();
@@ -296,4 +790,83 @@ mod tests {
             vec![Match::Exact(&4), Match::Prefix(&2), Match::Prefix(&3)]
         );
     }
+
+    #[test]
+    fn test_keyword_map_find_with_spans() {
+        let mut map = KeywordMap::new();
+        map.insert("hello world", 1);
+        map.insert("help desk", 2);
+
+        let mut results: Vec<_> = map.find_with_spans("hel").collect();
+        results.sort_by_key(|(m, _, _)| *m.as_ref());
+        assert_eq!(
+            results,
+            vec![
+                (Match::Prefix(&1), "hello", 0..5),
+                (Match::Prefix(&2), "help", 0..4),
+            ]
+        );
+
+        // A keyword equal to the query is reported as an exact match, with the span locating it
+        // within its key string.
+        let results: Vec<_> = map.find_with_spans("world").collect();
+        assert_eq!(results, vec![(Match::Exact(&1), "world", 6..11)]);
+    }
+
+    #[test]
+    fn test_keyword_map_unicode_tokenizer() {
+        let mut map = KeywordMap::with_tokenizer(Tokenizer::Unicode);
+        map.insert("café Москва", 1);
+        map.insert("ascii only", 2);
+
+        // Non-ASCII keywords are now indexed and searchable.
+        let results: Vec<_> = map.find_by_partial_keyword("Моск").collect();
+        assert_eq!(results, vec![Match::Prefix(&1)]);
+    }
+
+    #[test]
+    fn test_keyword_map_unicode_case_insensitive_tokenizer() {
+        let mut map = KeywordMap::with_tokenizer(Tokenizer::UnicodeCaseInsensitive);
+        map.insert("CAFÉ Bar", 1);
+
+        // Both keyword and query are case-folded, so a mixed-case query resolves the key without
+        // the caller having to lowercase it first.
+        let results: Vec<_> = map.find_by_partial_keyword("CAFÉ").collect();
+        assert_eq!(results, vec![Match::Prefix(&1)]);
+    }
+
+    #[test]
+    fn test_keyword_map_find_ranked() {
+        let mut map = KeywordMap::new();
+        map.insert("test", 1); // exact keyword match
+        map.insert("testing the tester", 2); // two prefix matches
+        map.insert("telephone", 3); // no match
+
+        let ranked = map.find_ranked("test");
+
+        // The exact match ranks first, the value with more matching keywords second, and the
+        // non-matching value is absent.
+        let values: Vec<_> = ranked.iter().map(|(m, _)| *m.as_ref()).collect();
+        assert_eq!(values, vec![&1, &2]);
+        assert!(matches!(ranked[0].0, Match::Exact(&1)));
+        assert!(ranked[0].1 > ranked[1].1);
+    }
+
+    #[test]
+    fn test_keyword_map_find_by_fuzzy_keyword() {
+        let mut map = KeywordMap::new();
+        map.insert("temperature sensor", 1);
+        map.insert("pressure gauge", 2);
+
+        // A single-character typo resolves within distance 1.
+        let results = map.find_by_fuzzy_keyword("temprature", 1);
+        assert_eq!(results, vec![Match::Fuzzy { distance: 1, value: &1 }]);
+
+        // An exact keyword is reported as an exact (distance 0) match, ahead of fuzzier ones.
+        let results = map.find_by_fuzzy_keyword("sensor", 2);
+        assert_eq!(results, vec![Match::Exact(&1)]);
+
+        // Nothing resolves when every keyword is further than the bound allows.
+        assert!(map.find_by_fuzzy_keyword("temprature", 0).is_empty());
+    }
 }